@@ -4,7 +4,7 @@ use std::{
         mpsc::{self, Receiver},
         Arc,
     },
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 use fyrox::{
@@ -23,26 +23,344 @@ use fyrox::{
     fxhash::{FxHashMap, FxHashSet},
     gui::{
         border::BorderBuilder,
+        button::{ButtonBuilder, ButtonMessage},
         check_box::{CheckBoxBuilder, CheckBoxMessage},
         constructor::new_widget_constructor_container,
         font::Font,
         grid::GridBuilder,
+        menu::{MenuItemBuilder, MenuItemContent, MenuItemMessage},
         message::{MessageDirection, UiMessage},
+        popup::{Popup, PopupBuilder},
         scroll_viewer::ScrollViewerBuilder,
         stack_panel::StackPanelBuilder,
         style::{resource::StyleResourceExt, Style, StyledProperty},
-        text::TextBuilder,
+        text::{TextBuilder, TextMessage},
+        text_box::TextBoxBuilder,
         utils::make_image_button_with_tooltip,
         widget::{WidgetBuilder, WidgetMessage},
-        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
-        VerticalAlignment,
+        BuildContext, HorizontalAlignment, Orientation, RcUiNodeHandle, Thickness, UiNode,
+        UserInterface, VerticalAlignment,
     },
     scene::graph::GraphUpdateSwitches,
     window::WindowAttributes,
 };
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use crate::{message::MessageSender, settings::Settings, GameLoopData, Message, FIXED_TIMESTEP};
 
+/// The current schema of [`LogWindowPersistentState`]. Bump this and add a branch to
+/// [`LogWindowPersistentState::migrate`] whenever the on-disk shape changes, so old files
+/// saved by a previous version of the editor keep loading instead of being discarded.
+const LOG_WINDOW_SETTINGS_FORMAT_VERSION: u32 = 2;
+
+/// Resolves `file_name` next to wherever the editor's own [`Settings`] are stored, instead
+/// of a path relative to the process's current working directory, so files the Debug Log
+/// window writes land somewhere predictable regardless of how the editor was launched.
+fn path_next_to_settings(file_name: &str) -> std::path::PathBuf {
+    Settings::default_path()
+        .parent()
+        .map(|dir| dir.join(file_name))
+        .unwrap_or_else(|| std::path::PathBuf::from(file_name))
+}
+
+/// Resolves next to wherever the editor's own [`Settings`] are stored, instead of a path
+/// relative to the process's current working directory, so the two files stay together
+/// regardless of how the editor was launched.
+fn log_window_settings_path() -> std::path::PathBuf {
+    path_next_to_settings("log_window_settings.json")
+}
+
+/// Everything about the Debug Log window that should survive a restart: the filter
+/// checkboxes, the message cap, the search query, and the physical window size. Saved
+/// alongside the editor's [`Settings`] so the window remembers how it was last configured.
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct LogWindowPersistentState {
+    #[serde(default = "default_log_window_settings_format_version")]
+    pub format_version: u32,
+    pub info_checked: bool,
+    pub warning_checked: bool,
+    pub error_checked: bool,
+    pub engine_checked: bool,
+    pub game_checked: bool,
+    pub regex_checked: bool,
+    #[serde(default)]
+    pub time_checked: bool,
+    pub search_query: String,
+    pub max_messages: usize,
+    pub window_width: u32,
+    pub window_height: u32,
+}
+
+fn default_log_window_settings_format_version() -> u32 {
+    LOG_WINDOW_SETTINGS_FORMAT_VERSION
+}
+
+impl Default for LogWindowPersistentState {
+    fn default() -> Self {
+        Self {
+            format_version: LOG_WINDOW_SETTINGS_FORMAT_VERSION,
+            info_checked: true,
+            warning_checked: true,
+            error_checked: true,
+            engine_checked: true,
+            game_checked: true,
+            regex_checked: false,
+            time_checked: false,
+            search_query: String::new(),
+            max_messages: 1000,
+            window_width: 600,
+            window_height: 800,
+        }
+    }
+}
+
+impl LogWindowPersistentState {
+    fn load() -> Self {
+        let Ok(contents) = std::fs::read_to_string(log_window_settings_path()) else {
+            return Self::default();
+        };
+        let Ok(mut state) = serde_json::from_str::<Self>(&contents) else {
+            return Self::default();
+        };
+        state.migrate();
+        state
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(log_window_settings_path(), json);
+        }
+    }
+
+    /// Upgrades a file written by an older `format_version` to the current schema.
+    /// Version 2 added `time_checked`; serde's `#[serde(default)]` already fills it in
+    /// as `false` for older files, so there is nothing further to backfill here yet.
+    fn migrate(&mut self) {
+        self.format_version = LOG_WINDOW_SETTINGS_FORMAT_VERSION;
+    }
+}
+
+/// A single entry mounted in the log stack panel: the folded `(kind, content)` key it
+/// currently displays, the handles of its widgets, and the state used to decide whether
+/// those widgets need to be touched again on the next reconciliation pass.
+pub(crate) struct MountedLogEntry {
+    key: (MessageKind, String),
+    border: Handle<UiNode>,
+    text_widget: Handle<UiNode>,
+    count: usize,
+    time: SystemTime,
+    is_even_row: bool,
+}
+
+/// The right-click context menu attached to every message row, built once and shared
+/// across all rows via the `Rc`-wrapped handle `with_context_menu` expects.
+pub(crate) struct LogContextMenu {
+    menu: RcUiNodeHandle,
+    copy: Handle<UiNode>,
+    copy_all_visible: Handle<UiNode>,
+    clear: Handle<UiNode>,
+}
+
+impl LogContextMenu {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let copy = MenuItemBuilder::new(WidgetBuilder::new())
+            .with_content(MenuItemContent::text("Copy"))
+            .build(ctx);
+        let copy_all_visible = MenuItemBuilder::new(WidgetBuilder::new())
+            .with_content(MenuItemContent::text("Copy All Visible"))
+            .build(ctx);
+        let clear = MenuItemBuilder::new(WidgetBuilder::new())
+            .with_content(MenuItemContent::text("Clear"))
+            .build(ctx);
+        let popup = PopupBuilder::new(WidgetBuilder::new())
+            .with_content(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(copy)
+                        .with_child(copy_all_visible)
+                        .with_child(clear),
+                )
+                .build(ctx),
+            )
+            .build(ctx);
+        let menu = RcUiNodeHandle::new(popup, ctx.sender());
+        Self {
+            menu,
+            copy,
+            copy_all_visible,
+            clear,
+        }
+    }
+}
+
+/// Which queue a [`TimestampedLogMessage`] arrived on, kept around so the structured log
+/// export can report it alongside kind/content/count/timestamp.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum LogMessageSource {
+    Engine,
+    Game,
+}
+
+impl LogMessageSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            LogMessageSource::Engine => "Engine",
+            LogMessageSource::Game => "Game",
+        }
+    }
+}
+
+/// A log message tagged with the wall-clock time it was received at and the queue it came
+/// from, so engine and game messages can be merged into a single chronological timeline
+/// instead of two separate streams while still remembering which was which.
+pub(crate) struct TimestampedLogMessage {
+    message: LogMessage,
+    time: SystemTime,
+    source: LogMessageSource,
+}
+
+/// Formats `time` as a `HH:MM:SS.mmm` time-of-day column. Falls back to all zeroes if the
+/// system clock is somehow set before the Unix epoch.
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let total_millis = since_epoch.as_millis();
+    let millis = total_millis % 1000;
+    let total_secs = total_millis / 1000;
+    let secs = total_secs % 60;
+    let mins = (total_secs / 60) % 60;
+    let hours = (total_secs / 3600) % 24;
+    format!("{hours:02}:{mins:02}:{secs:02}.{millis:03}")
+}
+
+/// Returns `true` if `content` matches `query` under the current regex/substring mode. An
+/// empty query always matches. In regex mode, `compiled` being `None` (the pattern is
+/// either empty or failed to compile, see [`LogChildOsWindow::set_search_query`]) falls
+/// back to a plain, case-insensitive substring match so the user never sees the log go
+/// blank while typing an incomplete pattern.
+fn text_matches_query(
+    content: &str,
+    query: &str,
+    regex_checked: bool,
+    compiled: Option<&Regex>,
+) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    if regex_checked {
+        if let Some(regex) = compiled {
+            return regex.is_match(content);
+        }
+    }
+    content.to_lowercase().contains(&query.to_lowercase())
+}
+
+/// Decides which of the two queues' next-up (newest-first) candidate to take given their
+/// front timestamps, ties going to the game queue. Returns `None` once both are exhausted.
+fn merge_take_from_game(
+    next_game: Option<SystemTime>,
+    next_engine: Option<SystemTime>,
+) -> Option<bool> {
+    match (next_game, next_engine) {
+        (Some(g), Some(e)) => Some(g >= e),
+        (Some(_), None) => Some(true),
+        (None, Some(_)) => Some(false),
+        (None, None) => None,
+    }
+}
+
+/// Folds duplicates by key, adjacent or not, out of an already-filtered, newest-first sequence
+/// of `(kind, content, time, source)` messages, returning `(kind, content, fold_count,
+/// most_recent_time, most_recent_source)` tuples in chronological order (oldest first) —
+/// the order they should be displayed in. The first occurrence seen for a key (walking
+/// newest-first) is necessarily its most recent occurrence, so that's the timestamp and
+/// source kept.
+fn fold_newest_first(
+    messages_newest_first: impl Iterator<Item = (MessageKind, String, SystemTime, LogMessageSource)>,
+) -> Vec<(MessageKind, String, usize, SystemTime, LogMessageSource)> {
+    let mut visited_messages: FxHashMap<
+        (MessageKind, String),
+        (usize, SystemTime, LogMessageSource),
+    > = FxHashMap::default();
+    let mut folded_messages_rev: Vec<(MessageKind, String)> = Vec::new();
+
+    for (kind, content, time, source) in messages_newest_first {
+        let key = (kind, content);
+        if let Some((count, _, _)) = visited_messages.get_mut(&key) {
+            *count += 1;
+        } else {
+            visited_messages.insert(key.clone(), (1, time, source));
+            folded_messages_rev.push(key);
+        }
+    }
+
+    folded_messages_rev
+        .into_iter()
+        .rev()
+        .map(|key| {
+            let (count, time, source) = visited_messages[&key];
+            (key.0, key.1, count, time, source)
+        })
+        .collect()
+}
+
+/// What [`plan_log_entry_reconciliation`] decided to do with one entry of the new folded
+/// message list: reuse the mounted entry already sitting at `old_index`, or build a fresh
+/// node because the key wasn't mounted at all.
+#[derive(Debug, PartialEq, Eq)]
+enum ReconcileStep {
+    Reuse { old_index: usize },
+    New,
+}
+
+/// Matches `new_keys` (the next folded message list, in display order) against `old_keys`
+/// (the currently mounted entries, in display order) purely by key, without touching any
+/// UI state. Returns one [`ReconcileStep`] per entry of `new_keys`, the indices into
+/// `old_keys` whose key no longer appears anywhere in `new_keys` (and so should be torn
+/// down), and whether the result needs a full relink pass.
+///
+/// A key can move: it might become the newest occurrence again after already being
+/// mounted further back (interleaved "X, Y, X" logging), or a previously hidden row can
+/// reappear mid-list when a filter checkbox is re-checked. Matching by key first — rather
+/// than assuming everything between the last match and the next one went stale — means
+/// those entries are reused in place instead of being destroyed and rebuilt. A relink is
+/// needed whenever a reused entry's original position comes before the last reused
+/// entry's, or a new node is built while older entries are still waiting to be matched.
+fn plan_log_entry_reconciliation<'a>(
+    old_keys: impl Iterator<Item = (MessageKind, &'a str)>,
+    new_keys: impl Iterator<Item = (MessageKind, &'a str)>,
+) -> (Vec<ReconcileStep>, Vec<usize>, bool) {
+    let mut remaining: FxHashMap<(MessageKind, &str), usize> = old_keys
+        .enumerate()
+        .map(|(index, key)| (key, index))
+        .collect();
+    let mut steps = Vec::new();
+    let mut needs_full_relink = false;
+    let mut last_old_index = None;
+
+    for key in new_keys {
+        match remaining.remove(&key) {
+            Some(old_index) => {
+                if last_old_index.is_some_and(|last| old_index < last) {
+                    needs_full_relink = true;
+                }
+                last_old_index = Some(old_index);
+                steps.push(ReconcileStep::Reuse { old_index });
+            }
+            None => {
+                if !remaining.is_empty() {
+                    needs_full_relink = true;
+                }
+                steps.push(ReconcileStep::New);
+            }
+        }
+    }
+
+    let mut stale_old_indices: Vec<usize> = remaining.into_values().collect();
+    stale_old_indices.sort_unstable();
+    (steps, stale_old_indices, needs_full_relink)
+}
+
 pub struct LogChildOsWindow {
     // graphics_context: GraphicsContext,
     pub(crate) engine: Engine,
@@ -56,24 +374,46 @@ pub struct LogChildOsWindow {
     pub(crate) error_checkbox: Handle<UiNode>,
     pub(crate) engine_checkbox: Handle<UiNode>,
     pub(crate) game_checkbox: Handle<UiNode>,
+    pub(crate) regex_checkbox: Handle<UiNode>,
+    pub(crate) time_checkbox: Handle<UiNode>,
+    pub(crate) search_text_box: Handle<UiNode>,
+    pub(crate) save_log_button: Handle<UiNode>,
+    pub(crate) save_log_json_button: Handle<UiNode>,
     pub(crate) info_checked: bool,
     pub(crate) warning_checked: bool,
     pub(crate) error_checked: bool,
     pub(crate) engine_checked: bool,
     pub(crate) game_checked: bool,
+    pub(crate) regex_checked: bool,
+    pub(crate) time_checked: bool,
+    pub(crate) search_query: String,
+    /// The compiled form of `search_query`, recompiled only when the query or the regex
+    /// toggle changes. `None` means regex mode is off, the query is empty, or it failed
+    /// to compile, in which case callers should fall back to a plain substring match.
+    pub(crate) compiled_search_regex: Option<Regex>,
     pub(crate) log_stack_panel: Handle<UiNode>,
-    pub(crate) messages_from_engine: VecDeque<LogMessage>,
-    pub(crate) messages_from_game: VecDeque<LogMessage>,
+    /// The UI nodes currently mounted under `log_stack_panel`, in display order, used to
+    /// incrementally reconcile against the next folded message list instead of tearing
+    /// everything down every update. See [`Self::update_log_message_model`].
+    pub(crate) mounted_log_entries: Vec<MountedLogEntry>,
+    pub(crate) context_menu: LogContextMenu,
+    pub(crate) messages_from_engine: VecDeque<TimestampedLogMessage>,
+    pub(crate) messages_from_game: VecDeque<TimestampedLogMessage>,
     pub(crate) max_messages: usize,
     pub(crate) graphics_context_initialized_once: bool,
 }
 
 impl LogChildOsWindow {
     pub fn new(log_message_receiver: Receiver<LogMessage>) -> Self {
+        let persisted = LogWindowPersistentState::load();
+
         let mut window_attributes = WindowAttributes::default();
         window_attributes.resizable = true;
         window_attributes.title = "Debug Log".to_string();
-        window_attributes.inner_size = Some(Size::Physical(PhysicalSize::new(600, 800)));
+        window_attributes.inner_size = Some(Size::Physical(PhysicalSize::new(
+            persisted.window_width,
+            persisted.window_height,
+        )));
 
         let graphics_context_params = GraphicsContextParams {
             window_attributes,
@@ -111,7 +451,12 @@ impl LogChildOsWindow {
         };
         let ctx = &mut engine.user_interfaces.first_mut().build_ctx();
 
-        fn build_checkbox(ctx: &mut BuildContext, text: &str, on_column: usize) -> Handle<UiNode> {
+        fn build_checkbox(
+            ctx: &mut BuildContext,
+            text: &str,
+            on_column: usize,
+            checked: bool,
+        ) -> Handle<UiNode> {
             CheckBoxBuilder::new(
                 WidgetBuilder::new()
                     .on_column(on_column)
@@ -127,14 +472,42 @@ impl LogChildOsWindow {
                 .with_text(text)
                 .build(ctx),
             )
-            .checked(Some(true))
+            .checked(Some(checked))
             .build(ctx)
         }
-        let info_checkbox = build_checkbox(ctx, "Info", 0);
-        let warning_checkbox = build_checkbox(ctx, "Warning", 1);
-        let error_checkbox = build_checkbox(ctx, "Error", 2);
-        let engine_checkbox = build_checkbox(ctx, "Engine", 3);
-        let game_checkbox = build_checkbox(ctx, "Game", 4);
+        let info_checkbox = build_checkbox(ctx, "Info", 0, persisted.info_checked);
+        let warning_checkbox = build_checkbox(ctx, "Warning", 1, persisted.warning_checked);
+        let error_checkbox = build_checkbox(ctx, "Error", 2, persisted.error_checked);
+        let engine_checkbox = build_checkbox(ctx, "Engine", 3, persisted.engine_checked);
+        let game_checkbox = build_checkbox(ctx, "Game", 4, persisted.game_checked);
+        let regex_checkbox = build_checkbox(ctx, "Regex", 5, persisted.regex_checked);
+        let time_checkbox = build_checkbox(ctx, "Time", 6, persisted.time_checked);
+        let search_text_box = TextBoxBuilder::new(
+            WidgetBuilder::new()
+                .on_column(7)
+                .with_width(150.0)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .with_text(persisted.search_query.clone())
+        .build(ctx);
+        let save_log_button = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .on_column(8)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .with_text("Save Log...")
+        .build(ctx);
+        let save_log_json_button = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .on_column(9)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .with_text("Save Log as JSON...")
+        .build(ctx);
+        let context_menu = LogContextMenu::new(ctx);
         let log_stack_panel = StackPanelBuilder::new(
             WidgetBuilder::new()
                 .with_margin(Thickness::uniform(1.0))
@@ -156,7 +529,12 @@ impl LogChildOsWindow {
                             .with_child(warning_checkbox)
                             .with_child(error_checkbox)
                             .with_child(engine_checkbox)
-                            .with_child(game_checkbox),
+                            .with_child(game_checkbox)
+                            .with_child(regex_checkbox)
+                            .with_child(time_checkbox)
+                            .with_child(search_text_box)
+                            .with_child(save_log_button)
+                            .with_child(save_log_json_button),
                     )
                     .build(ctx),
                 )
@@ -172,14 +550,20 @@ impl LogChildOsWindow {
                     .with_vertical_scroll_allowed(true)
                     .build(ctx),
                 )
-                .with_width(600.0)
-                .with_height(800.0)
+                .with_width(persisted.window_width as f32)
+                .with_height(persisted.window_height as f32)
                 .with_background(ctx.style.property(Style::BRUSH_DIM_BLUE)),
         )
         .with_orientation(Orientation::Vertical)
         .build(ctx);
 
-        let max_messages = 1000; // hardcoded limit for now
+        let max_messages = persisted.max_messages;
+        let mut compiled_search_regex = None;
+        let regex_checked = persisted.regex_checked;
+        let search_query = persisted.search_query.clone();
+        if regex_checked && !search_query.is_empty() {
+            compiled_search_regex = Regex::new(&search_query).ok();
+        }
         Self {
             graphics_context_initialized_once: false,
             engine,
@@ -193,106 +577,296 @@ impl LogChildOsWindow {
             error_checkbox,
             engine_checkbox,
             game_checkbox,
+            regex_checkbox,
+            time_checkbox,
+            search_text_box,
+            save_log_button,
+            save_log_json_button,
             log_stack_panel,
+            mounted_log_entries: Vec::new(),
+            context_menu,
             messages_from_engine: VecDeque::new(),
             messages_from_game: VecDeque::new(),
             max_messages,
-            info_checked: true,
-            warning_checked: true,
-            error_checked: true,
-            engine_checked: true,
-            game_checked: true,
+            info_checked: persisted.info_checked,
+            warning_checked: persisted.warning_checked,
+            error_checked: persisted.error_checked,
+            engine_checked: persisted.engine_checked,
+            game_checked: persisted.game_checked,
+            regex_checked,
+            time_checked: persisted.time_checked,
+            search_query,
+            compiled_search_regex,
         }
     }
 
-    /// The log message UI nodes to be rendered are treated as stateless, meaning that
-    /// whenever the log messages change, we remove all message nodes from the stack panel
-    /// and recreate them from scratch.
-    ///
-    /// The performance overhead is negligible compared with the actual game.
+    /// Snapshots the current filter state, message cap, search query, and window size
+    /// into a [`LogWindowPersistentState`] and writes it to disk.
+    fn persist_settings(&self) {
+        let (window_width, window_height) =
+            if let GraphicsContext::Initialized(ctx) = &self.engine.graphics_context {
+                let size = ctx.window.inner_size();
+                (size.width, size.height)
+            } else {
+                (600, 800)
+            };
+        LogWindowPersistentState {
+            format_version: LOG_WINDOW_SETTINGS_FORMAT_VERSION,
+            info_checked: self.info_checked,
+            warning_checked: self.warning_checked,
+            error_checked: self.error_checked,
+            engine_checked: self.engine_checked,
+            game_checked: self.game_checked,
+            regex_checked: self.regex_checked,
+            time_checked: self.time_checked,
+            search_query: self.search_query.clone(),
+            max_messages: self.max_messages,
+            window_width,
+            window_height,
+        }
+        .save();
+    }
+
+    /// Returns `true` if `content` should be shown given the current search query and
+    /// regex toggle. An empty query always matches. In regex mode, a pattern that fails
+    /// to compile (see [`Self::set_search_query`]) silently falls back to a plain,
+    /// case-insensitive substring match so the user never sees the log go blank while typing.
+    fn matches_search(&self, content: &str) -> bool {
+        text_matches_query(
+            content,
+            &self.search_query,
+            self.regex_checked,
+            self.compiled_search_regex.as_ref(),
+        )
+    }
+
+    /// Updates the stored search query and recompiles the cached [`Regex`] when in regex
+    /// mode. Compilation happens once per query change rather than once per log message.
+    fn set_search_query(&mut self, query: String) {
+        self.search_query = query;
+        self.recompile_search_regex();
+    }
+
+    fn recompile_search_regex(&mut self) {
+        self.compiled_search_regex = if self.regex_checked && !self.search_query.is_empty() {
+            Regex::new(&self.search_query).ok()
+        } else {
+            None
+        };
+    }
+
+    /// Applies the current kind/source/search filters, merges the engine and game queues
+    /// into a single time-ordered sequence (instead of concatenating game after engine),
+    /// and folds duplicates by key (adjacent or not), returning `(kind, content, fold_count,
+    /// most_recent_time, most_recent_source)` tuples in chronological order (oldest
+    /// first), which is also the order they should be displayed in.
+    fn compute_folded_messages(
+        &self,
+    ) -> Vec<(MessageKind, String, usize, SystemTime, LogMessageSource)> {
+        // Walk both queues newest-to-oldest, always taking whichever front candidate is
+        // more recent, so the merged sequence reflects the order messages actually arrived
+        // in rather than one source's backlog dumped after the other's.
+        let mut game_iter = self.messages_from_game.iter().rev().peekable();
+        let mut engine_iter = self.messages_from_engine.iter().rev().peekable();
+        let merged_newest_first = std::iter::from_fn(|| {
+            let next_game = if self.game_checked {
+                game_iter.peek().map(|m| m.time)
+            } else {
+                None
+            };
+            let next_engine = if self.engine_checked {
+                engine_iter.peek().map(|m| m.time)
+            } else {
+                None
+            };
+            match merge_take_from_game(next_game, next_engine)? {
+                true => game_iter.next(),
+                false => engine_iter.next(),
+            }
+        })
+        .filter(|message| {
+            (message.message.kind != MessageKind::Information || self.info_checked)
+                && (message.message.kind != MessageKind::Warning || self.warning_checked)
+                && (message.message.kind != MessageKind::Error || self.error_checked)
+                && self.matches_search(&message.message.content)
+        })
+        .map(|message| {
+            (
+                message.message.kind,
+                message.message.content.clone(),
+                message.time,
+                message.source,
+            )
+        });
+
+        fold_newest_first(merged_newest_first)
+    }
+
+    /// Formats a row's display text, optionally prefixed with its (most recent) timestamp.
+    fn format_entry_text(
+        content: &str,
+        count: usize,
+        time: SystemTime,
+        time_checked: bool,
+    ) -> String {
+        let folded = if count > 1 {
+            format!("{} (x{})", content, count)
+        } else {
+            content.to_string()
+        };
+        if time_checked {
+            format!("[{}] {}", format_timestamp(time), folded)
+        } else {
+            folded
+        }
+    }
+
+    /// Rebuilds the folded message list and reconciles it against `mounted_log_entries`
+    /// instead of tearing the whole stack panel down. [`plan_log_entry_reconciliation`]
+    /// matches the new list against the old one by key alone, so entries are reused in
+    /// place (re-texting only if the fold count changed, and re-backgrounding only if the
+    /// alternating row parity changed) regardless of whether they moved — including a key
+    /// becoming the newest occurrence again or a hidden row reappearing mid-list when a
+    /// filter checkbox is re-checked; only genuinely new keys get a freshly built node,
+    /// and only keys that vanished entirely are removed. Because folding already collapses
+    /// duplicates, the common case of "same tail, one new line" touches at most a couple
+    /// of widgets and the scroll position is preserved.
     ///
-    /// In this way, it is much more maintainable and supports functionalities like folding duplicate log messages
-    /// with much simpler implementation.
+    /// Freshly built nodes are always linked at the tail of `log_stack_panel` first, which
+    /// is only correct if they happen to belong there. Whenever the plan reports that a
+    /// reused entry moved out of order, or a new node was built while older entries were
+    /// still waiting to be matched, a single cheap relink pass re-sends every entry's
+    /// existing `WidgetMessage::link` in final order afterwards. This only moves
+    /// already-built widgets; it never rebuilds them, so the common append-only case still
+    /// touches nothing extra.
     fn update_log_message_model(&mut self) {
+        let folded = self.compute_folded_messages();
+        let time_checked = self.time_checked;
+        let old_entries = std::mem::take(&mut self.mounted_log_entries);
+        let (steps, stale_old_indices, needs_full_relink) = plan_log_entry_reconciliation(
+            old_entries
+                .iter()
+                .map(|entry| (entry.key.0, entry.key.1.as_str())),
+            folded
+                .iter()
+                .map(|(kind, content, ..)| (*kind, content.as_str())),
+        );
+        let mut old_entries: Vec<Option<MountedLogEntry>> =
+            old_entries.into_iter().map(Some).collect();
+        let mut new_entries: Vec<MountedLogEntry> = Vec::with_capacity(folded.len());
         let user_interface = self.engine.user_interfaces.first_mut();
-        let log_stack_panel_ref = user_interface.node_mut(self.log_stack_panel);
-        let children = log_stack_panel_ref.children().to_vec();
-        for child in children {
-            user_interface.send_message(WidgetMessage::remove(child, MessageDirection::ToWidget));
+
+        for stale_index in stale_old_indices {
+            let stale = old_entries[stale_index]
+                .take()
+                .expect("plan_log_entry_reconciliation returns each stale index at most once");
+            user_interface.send_message(WidgetMessage::remove(
+                stale.border,
+                MessageDirection::ToWidget,
+            ));
         }
 
-        // let log_stack_panel_ref = user_interface.node_mut(self.log_stack_panel);
-        // assert!(log_stack_panel_ref.children().is_empty());
-        //
-        let mut visited_messages: FxHashMap<(MessageKind, String), usize> = FxHashMap::default();
-        let mut folded_messages_rev: Vec<(MessageKind, String)> = Vec::new();
-        for (checked, messages) in [
-            (self.game_checked, &self.messages_from_game),
-            (self.engine_checked, &self.messages_from_engine),
-        ] {
-            if checked {
-                for message in messages.iter().rev() {
-                    if (message.kind == MessageKind::Information && !self.info_checked)
-                        || (message.kind == MessageKind::Warning && !self.warning_checked)
-                        || (message.kind == MessageKind::Error && !self.error_checked)
-                    {
-                        continue;
+        for ((new_index, (kind, content, count, time, _source)), step) in
+            folded.into_iter().enumerate().zip(steps)
+        {
+            let key = (kind, content);
+            let is_even_row = new_index % 2 == 0;
+
+            let entry = match step {
+                ReconcileStep::New => {
+                    let ctx = &mut user_interface.build_ctx();
+                    let text = Self::format_entry_text(&key.1, count, time, time_checked);
+                    let text_widget = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_context_menu(self.context_menu.menu.clone())
+                            .with_margin(Thickness::uniform(2.0))
+                            .with_foreground(match key.0 {
+                                MessageKind::Information => {
+                                    ctx.style.property(Style::BRUSH_INFORMATION)
+                                }
+                                MessageKind::Warning => {
+                                    ctx.style.property(Style::BRUSH_WARNING)
+                                }
+                                MessageKind::Error => ctx.style.property(Style::BRUSH_ERROR),
+                            }),
+                    )
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .with_text(text)
+                    .build(ctx);
+                    // This is copied from the LogPanel.
+                    let border = BorderBuilder::new(
+                        WidgetBuilder::new()
+                            .with_background(if is_even_row {
+                                ctx.style.property(Style::BRUSH_LIGHT)
+                            } else {
+                                ctx.style.property(Style::BRUSH_DARK)
+                            })
+                            .with_child(text_widget),
+                    )
+                    .build(ctx);
+                    user_interface.send_message(WidgetMessage::link(
+                        border,
+                        MessageDirection::ToWidget,
+                        self.log_stack_panel,
+                    ));
+                    MountedLogEntry {
+                        key,
+                        border,
+                        text_widget,
+                        count,
+                        time,
+                        is_even_row,
+                    }
+                }
+                ReconcileStep::Reuse { old_index } => {
+                    let mut entry = old_entries[old_index].take().expect(
+                        "plan_log_entry_reconciliation returns each old_index at most once",
+                    );
+                    if entry.count != count {
+                        let text =
+                            Self::format_entry_text(&entry.key.1, count, time, time_checked);
+                        user_interface.send_message(TextMessage::text(
+                            entry.text_widget,
+                            MessageDirection::ToWidget,
+                            text,
+                        ));
+                        entry.count = count;
+                        entry.time = time;
                     }
-                    let key = (message.kind, message.content.clone());
-                    if let Some(count) = visited_messages.get_mut(&key) {
-                        *count += 1;
-                    } else {
-                        visited_messages.insert(key.clone(), 1);
-                        folded_messages_rev.push(key);
+                    if entry.is_even_row != is_even_row {
+                        let ctx = &mut user_interface.build_ctx();
+                        let brush = if is_even_row {
+                            ctx.style.property(Style::BRUSH_LIGHT)
+                        } else {
+                            ctx.style.property(Style::BRUSH_DARK)
+                        };
+                        user_interface.send_message(WidgetMessage::background(
+                            entry.border,
+                            MessageDirection::ToWidget,
+                            brush,
+                        ));
+                        entry.is_even_row = is_even_row;
                     }
+                    entry
                 }
-            }
+            };
+            new_entries.push(entry);
         }
-        // create the actual text UI nodes
 
-        for (index, message) in folded_messages_rev.into_iter().rev().enumerate() {
-            let ctx = &mut user_interface.build_ctx();
-            let count = visited_messages[&message];
-            let text = if count > 1 {
-                format!("{} (x{})", message.1, count)
-            } else {
-                message.1.clone()
-            };
-            // This is copied from the LogPanel.
-            let item = BorderBuilder::new(
-                WidgetBuilder::new()
-                    .with_background(if index % 2 == 0 {
-                        ctx.style.property(Style::BRUSH_LIGHT)
-                    } else {
-                        ctx.style.property(Style::BRUSH_DARK)
-                    })
-                    .with_child(
-                        TextBuilder::new(
-                            WidgetBuilder::new()
-                                // .with_context_menu(self.context_menu.menu.clone())
-                                .with_margin(Thickness::uniform(2.0))
-                                .with_foreground(match message.0 {
-                                    MessageKind::Information => {
-                                        ctx.style.property(Style::BRUSH_INFORMATION)
-                                    }
-                                    MessageKind::Warning => {
-                                        ctx.style.property(Style::BRUSH_WARNING)
-                                    }
-                                    MessageKind::Error => ctx.style.property(Style::BRUSH_ERROR),
-                                }),
-                        )
-                        .with_vertical_text_alignment(VerticalAlignment::Center)
-                        .with_text(text)
-                        .build(ctx),
-                    ),
-            )
-            .build(ctx);
-            user_interface.send_message(WidgetMessage::link(
-                item,
-                MessageDirection::ToWidget,
-                self.log_stack_panel,
-            ))
+        if needs_full_relink {
+            // `WidgetMessage::link` moves an already-linked widget rather than duplicating
+            // it, so re-sending it for every entry in `new_entries`' order simply reorders
+            // the existing children to match instead of rebuilding anything.
+            for entry in &new_entries {
+                user_interface.send_message(WidgetMessage::link(
+                    entry.border,
+                    MessageDirection::ToWidget,
+                    self.log_stack_panel,
+                ));
+            }
         }
+
+        self.mounted_log_entries = new_entries;
     }
 
     /// The update function
@@ -308,14 +882,23 @@ impl LogChildOsWindow {
         // receive messages from the log
         while let Ok(mut log_message) = self.log_message_receiver.try_recv() {
             received_anything = true;
+            let time = SystemTime::now();
             if log_message.content.contains("[__GAME__]") {
                 log_message.content = log_message.content.replace("[__GAME__]", "");
-                self.messages_from_game.push_back(log_message);
+                self.messages_from_game.push_back(TimestampedLogMessage {
+                    message: log_message,
+                    time,
+                    source: LogMessageSource::Game,
+                });
                 if self.messages_from_game.len() > self.max_messages {
                     self.messages_from_game.pop_front();
                 }
             } else {
-                self.messages_from_engine.push_back(log_message);
+                self.messages_from_engine.push_back(TimestampedLogMessage {
+                    message: log_message,
+                    time,
+                    source: LogMessageSource::Engine,
+                });
                 if self.messages_from_engine.len() > self.max_messages {
                     self.messages_from_engine.pop_front();
                 }
@@ -367,6 +950,7 @@ impl LogChildOsWindow {
     }
 
     pub fn on_suspended(&mut self) {
+        self.persist_settings();
         self.engine.destroy_graphics_context().unwrap();
     }
 
@@ -431,9 +1015,526 @@ impl LogChildOsWindow {
                 &self.game_checkbox,
                 &mut self.game_checked,
             );
+            let mut regex_checked = self.regex_checked;
+            handle_check_changed(
+                message,
+                checkbox_message,
+                &self.regex_checkbox,
+                &mut regex_checked,
+            );
+            if regex_checked != self.regex_checked {
+                self.regex_checked = regex_checked;
+                self.recompile_search_regex();
+            }
+            let mut time_checked = self.time_checked;
+            handle_check_changed(
+                message,
+                checkbox_message,
+                &self.time_checkbox,
+                &mut time_checked,
+            );
+            if time_checked != self.time_checked {
+                self.time_checked = time_checked;
+                // Every row's text includes the timestamp prefix only when this is on, so
+                // toggling it invalidates text that the normal count-based diff wouldn't
+                // otherwise catch. Tearing down the mounted entries forces a full re-render.
+                let user_interface = self.engine.user_interfaces.first_mut();
+                for stale in self.mounted_log_entries.drain(..) {
+                    user_interface.send_message(WidgetMessage::remove(
+                        stale.border,
+                        MessageDirection::ToWidget,
+                    ));
+                }
+            }
             // If we receive a CheckBoxMessage, it means one of the checkboxes changed state.
             message_model_requires_update = true;
+            self.persist_settings();
+        }
+        if let Some(TextMessage::Text(text)) = message.data::<TextMessage>() {
+            if message.destination() == self.search_text_box
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.set_search_query(text.clone());
+                message_model_requires_update = true;
+                self.persist_settings();
+            }
+        }
+        if let Some(MenuItemMessage::Click) = message.data::<MenuItemMessage>() {
+            if message.destination() == self.context_menu.copy {
+                let text = self.context_menu_target_entry().map(|entry| {
+                    Self::format_entry_text(
+                        &entry.key.1,
+                        entry.count,
+                        entry.time,
+                        self.time_checked,
+                    )
+                });
+                if let Some(text) = text {
+                    self.copy_to_clipboard(text);
+                }
+            } else if message.destination() == self.context_menu.copy_all_visible {
+                let time_checked = self.time_checked;
+                let text = self
+                    .mounted_log_entries
+                    .iter()
+                    .map(|entry| {
+                        Self::format_entry_text(&entry.key.1, entry.count, entry.time, time_checked)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.copy_to_clipboard(text);
+            } else if message.destination() == self.context_menu.clear {
+                self.messages_from_engine.clear();
+                self.messages_from_game.clear();
+                message_model_requires_update = true;
+            }
+        }
+        if let Some(ButtonMessage::Click) = message.data::<ButtonMessage>() {
+            if message.destination() == self.save_log_button {
+                self.save_log_to_timestamped_file("txt");
+            } else if message.destination() == self.save_log_json_button {
+                self.save_log_to_timestamped_file("jsonl");
+            }
         }
         message_model_requires_update
     }
+
+    /// Copies `text` to the OS clipboard via the engine's user interface clipboard
+    /// facility.
+    fn copy_to_clipboard(&mut self, text: String) {
+        self.engine
+            .user_interfaces
+            .first_mut()
+            .set_clipboard_text(&text);
+    }
+
+    /// Looks up the mounted row the context menu was opened on, using the popup's
+    /// placement target (the text widget that had `with_context_menu` attached).
+    fn context_menu_target_entry(&self) -> Option<&MountedLogEntry> {
+        let user_interface = self.engine.user_interfaces.first();
+        let popup = user_interface
+            .try_get(self.context_menu.menu.handle())?
+            .cast::<Popup>()?;
+        let target = popup.placement.target();
+        self.mounted_log_entries
+            .iter()
+            .find(|entry| entry.text_widget == target)
+    }
+
+    /// Saves the currently-visible log to `fyrox_log_<timestamp>.<extension>` next to
+    /// wherever the editor's own [`Settings`] are stored (instead of the process's current
+    /// working directory), picking the plain or structured
+    /// [`export_visible_log`](Self::export_visible_log) format based on `extension`
+    /// (`"txt"` for the `Save Log...` button, `"jsonl"` for `Save Log as JSON...`).
+    fn save_log_to_timestamped_file(&self, extension: &str) {
+        let path = path_next_to_settings(&format!(
+            "fyrox_log_{}.{extension}",
+            format_timestamp(std::time::SystemTime::now()).replace(':', "-")
+        ));
+        if let Err(error) = self.export_visible_log(&path) {
+            println!("Failed to save log to {}: {error}", path.display());
+        } else {
+            println!("Saved visible log to {}", path.display());
+        }
+    }
+
+    /// Writes the currently-visible (filtered + folded) log to `path`, reusing the same
+    /// filtering/folding logic the rendered model is built from. A `.json`/`.jsonl`
+    /// extension selects one structured JSON object per line (kind, source, count,
+    /// timestamp); any other extension writes plain `[kind] (xN) content` lines.
+    fn export_visible_log(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let folded = self.compute_folded_messages();
+        let structured = path_wants_structured_export(path);
+        let mut contents = String::new();
+        for (kind, content, count, time, source) in &folded {
+            contents.push_str(&format_export_line(
+                *kind, content, *count, *time, *source, structured,
+            ));
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+/// Returns `true` if `path`'s extension (`.json`/`.jsonl`) selects the structured export
+/// format rather than the plain `[kind] (xN) content` text format.
+fn path_wants_structured_export(path: &std::path::Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("json") | Some("jsonl")
+    )
+}
+
+/// Renders a single folded log row as one line of the plain or structured export format,
+/// matching whichever `structured` selects. Structured lines that fail to serialize are
+/// skipped rather than corrupting the file with partial JSON.
+fn format_export_line(
+    kind: MessageKind,
+    content: &str,
+    count: usize,
+    time: SystemTime,
+    source: LogMessageSource,
+    structured: bool,
+) -> String {
+    if structured {
+        let line = ExportedLogLine {
+            kind: match kind {
+                MessageKind::Information => "Information",
+                MessageKind::Warning => "Warning",
+                MessageKind::Error => "Error",
+            },
+            source: source.as_str(),
+            content,
+            count,
+            time: format_timestamp(time),
+        };
+        match serde_json::to_string(&line) {
+            Ok(json) => format!("{json}\n"),
+            Err(_) => String::new(),
+        }
+    } else {
+        format!("[{:?}] (x{}) {}\n", kind, count, content)
+    }
+}
+
+/// One line of the structured (`.json`/`.jsonl`) export format written by
+/// [`LogChildOsWindow::export_visible_log`].
+#[derive(Serialize)]
+struct ExportedLogLine<'a> {
+    kind: &'a str,
+    source: &'a str,
+    content: &'a str,
+    count: usize,
+    time: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn time_at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn log_window_persistent_state_round_trips_through_json() {
+        let state = LogWindowPersistentState {
+            search_query: "panic".to_string(),
+            max_messages: 500,
+            ..LogWindowPersistentState::default()
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let deserialized: LogWindowPersistentState = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.search_query, "panic");
+        assert_eq!(deserialized.max_messages, 500);
+        assert_eq!(
+            deserialized.format_version,
+            LOG_WINDOW_SETTINGS_FORMAT_VERSION
+        );
+    }
+
+    #[test]
+    fn log_window_persistent_state_migrates_a_v1_file_missing_time_checked() {
+        // A file saved before `time_checked` existed has no such key, so it must come from
+        // its `#[serde(default)]`, and `migrate()` must leave `format_version` at the
+        // current schema (it's already filled in by its own `#[serde(default)]`).
+        let v1_json = r#"{
+            "info_checked": true,
+            "warning_checked": true,
+            "error_checked": true,
+            "engine_checked": true,
+            "game_checked": true,
+            "regex_checked": false,
+            "search_query": "",
+            "max_messages": 1000,
+            "window_width": 600,
+            "window_height": 800
+        }"#;
+        let mut state: LogWindowPersistentState = serde_json::from_str(v1_json).unwrap();
+        assert!(!state.time_checked);
+
+        state.migrate();
+        assert_eq!(state.format_version, LOG_WINDOW_SETTINGS_FORMAT_VERSION);
+        assert!(!state.time_checked);
+    }
+
+    #[test]
+    fn text_matches_query_empty_query_always_matches() {
+        assert!(text_matches_query("anything", "", false, None));
+        assert!(text_matches_query("anything", "", true, None));
+    }
+
+    #[test]
+    fn text_matches_query_substring_mode_is_case_insensitive() {
+        assert!(text_matches_query("Hello World", "world", false, None));
+        assert!(!text_matches_query("Hello World", "bye", false, None));
+    }
+
+    #[test]
+    fn text_matches_query_regex_mode_uses_compiled_pattern() {
+        let compiled = Regex::new("^err.*42$").unwrap();
+        assert!(text_matches_query(
+            "err code 42",
+            "^err.*42$",
+            true,
+            Some(&compiled)
+        ));
+        assert!(!text_matches_query(
+            "info code 42",
+            "^err.*42$",
+            true,
+            Some(&compiled)
+        ));
+    }
+
+    #[test]
+    fn text_matches_query_regex_mode_falls_back_to_substring_when_uncompiled() {
+        // `compiled` is `None` here, simulating a pattern that hasn't compiled yet.
+        assert!(text_matches_query("Hello World", "world", true, None));
+    }
+
+    #[test]
+    fn merge_take_from_game_prefers_more_recent_timestamp() {
+        assert_eq!(
+            merge_take_from_game(Some(time_at(5)), Some(time_at(2))),
+            Some(true)
+        );
+        assert_eq!(
+            merge_take_from_game(Some(time_at(2)), Some(time_at(5))),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn merge_take_from_game_breaks_ties_towards_game() {
+        assert_eq!(
+            merge_take_from_game(Some(time_at(3)), Some(time_at(3))),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn merge_take_from_game_handles_one_side_exhausted() {
+        assert_eq!(merge_take_from_game(Some(time_at(1)), None), Some(true));
+        assert_eq!(merge_take_from_game(None, Some(time_at(1))), Some(false));
+        assert_eq!(merge_take_from_game(None, None), None);
+    }
+
+    #[test]
+    fn fold_newest_first_counts_duplicates_and_keeps_most_recent_time() {
+        let messages = vec![
+            (
+                MessageKind::Information,
+                "hello".to_string(),
+                time_at(3),
+                LogMessageSource::Engine,
+            ),
+            (
+                MessageKind::Information,
+                "hello".to_string(),
+                time_at(2),
+                LogMessageSource::Game,
+            ),
+            (
+                MessageKind::Warning,
+                "uh oh".to_string(),
+                time_at(1),
+                LogMessageSource::Game,
+            ),
+        ];
+        let folded = fold_newest_first(messages.into_iter());
+        assert_eq!(
+            folded,
+            vec![
+                (
+                    MessageKind::Warning,
+                    "uh oh".to_string(),
+                    1,
+                    time_at(1),
+                    LogMessageSource::Game
+                ),
+                (
+                    MessageKind::Information,
+                    "hello".to_string(),
+                    2,
+                    time_at(3),
+                    LogMessageSource::Engine
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fold_newest_first_reorders_a_key_promoted_to_most_recent() {
+        // "hello" was originally oldest, but its freshest occurrence (walking
+        // newest-first, so listed first here) now makes it the most recent entry.
+        let messages = vec![
+            (
+                MessageKind::Information,
+                "hello".to_string(),
+                time_at(10),
+                LogMessageSource::Engine,
+            ),
+            (
+                MessageKind::Warning,
+                "uh oh".to_string(),
+                time_at(5),
+                LogMessageSource::Game,
+            ),
+            (
+                MessageKind::Information,
+                "hello".to_string(),
+                time_at(1),
+                LogMessageSource::Game,
+            ),
+        ];
+        let folded = fold_newest_first(messages.into_iter());
+        assert_eq!(
+            folded,
+            vec![
+                (
+                    MessageKind::Warning,
+                    "uh oh".to_string(),
+                    1,
+                    time_at(5),
+                    LogMessageSource::Game
+                ),
+                (
+                    MessageKind::Information,
+                    "hello".to_string(),
+                    2,
+                    time_at(10),
+                    LogMessageSource::Engine
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_wants_structured_export_matches_json_extensions_only() {
+        assert!(path_wants_structured_export(std::path::Path::new(
+            "log.json"
+        )));
+        assert!(path_wants_structured_export(std::path::Path::new(
+            "log.jsonl"
+        )));
+        assert!(!path_wants_structured_export(std::path::Path::new(
+            "log.txt"
+        )));
+        assert!(!path_wants_structured_export(std::path::Path::new("log")));
+    }
+
+    #[test]
+    fn format_export_line_plain_includes_kind_count_and_content() {
+        let line = format_export_line(
+            MessageKind::Warning,
+            "uh oh",
+            3,
+            time_at(1),
+            LogMessageSource::Game,
+            false,
+        );
+        assert_eq!(line, "[Warning] (x3) uh oh\n");
+    }
+
+    #[test]
+    fn format_export_line_structured_is_one_json_object_per_line() {
+        let line = format_export_line(
+            MessageKind::Error,
+            "boom",
+            2,
+            time_at(1),
+            LogMessageSource::Engine,
+            true,
+        );
+        let parsed: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(parsed["kind"], "Error");
+        assert_eq!(parsed["source"], "Engine");
+        assert_eq!(parsed["content"], "boom");
+        assert_eq!(parsed["count"], 2);
+    }
+
+    #[test]
+    fn plan_log_entry_reconciliation_appends_new_tail_without_relinking() {
+        let old_keys = [
+            (MessageKind::Information, "a"),
+            (MessageKind::Information, "b"),
+        ];
+        let new_keys = [
+            (MessageKind::Information, "a"),
+            (MessageKind::Information, "b"),
+            (MessageKind::Information, "c"),
+        ];
+        let (steps, stale, needs_full_relink) =
+            plan_log_entry_reconciliation(old_keys.into_iter(), new_keys.into_iter());
+        assert_eq!(
+            steps,
+            vec![
+                ReconcileStep::Reuse { old_index: 0 },
+                ReconcileStep::Reuse { old_index: 1 },
+                ReconcileStep::New,
+            ]
+        );
+        assert!(stale.is_empty());
+        assert!(!needs_full_relink);
+    }
+
+    #[test]
+    fn plan_log_entry_reconciliation_reuses_a_key_promoted_back_to_newest() {
+        // Mounted order is [X, Y], but an interleaved "X, Y, X" logging pattern folds to
+        // [Y, X] with X's count bumped — X must be relinked, not destroyed and rebuilt.
+        let old_keys = [(MessageKind::Information, "x"), (MessageKind::Warning, "y")];
+        let new_keys = [(MessageKind::Warning, "y"), (MessageKind::Information, "x")];
+        let (steps, stale, needs_full_relink) =
+            plan_log_entry_reconciliation(old_keys.into_iter(), new_keys.into_iter());
+        assert_eq!(
+            steps,
+            vec![
+                ReconcileStep::Reuse { old_index: 1 },
+                ReconcileStep::Reuse { old_index: 0 },
+            ]
+        );
+        assert!(stale.is_empty());
+        assert!(needs_full_relink);
+    }
+
+    #[test]
+    fn plan_log_entry_reconciliation_removes_keys_that_disappeared() {
+        let old_keys = [
+            (MessageKind::Information, "a"),
+            (MessageKind::Warning, "rolled off"),
+        ];
+        let new_keys = [(MessageKind::Information, "a")];
+        let (steps, stale, needs_full_relink) =
+            plan_log_entry_reconciliation(old_keys.into_iter(), new_keys.into_iter());
+        assert_eq!(steps, vec![ReconcileStep::Reuse { old_index: 0 }]);
+        assert_eq!(stale, vec![1]);
+        assert!(!needs_full_relink);
+    }
+
+    #[test]
+    fn plan_log_entry_reconciliation_reinserts_a_hidden_row_mid_list() {
+        // "b" was filtered out and is re-checked back in, reappearing between "a" and "c".
+        let old_keys = [
+            (MessageKind::Information, "a"),
+            (MessageKind::Information, "c"),
+        ];
+        let new_keys = [
+            (MessageKind::Information, "a"),
+            (MessageKind::Information, "b"),
+            (MessageKind::Information, "c"),
+        ];
+        let (steps, stale, needs_full_relink) =
+            plan_log_entry_reconciliation(old_keys.into_iter(), new_keys.into_iter());
+        assert_eq!(
+            steps,
+            vec![
+                ReconcileStep::Reuse { old_index: 0 },
+                ReconcileStep::New,
+                ReconcileStep::Reuse { old_index: 1 },
+            ]
+        );
+        assert!(stale.is_empty());
+        assert!(needs_full_relink);
+    }
 }